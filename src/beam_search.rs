@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use crate::types::{Evaluator, Mutation, State};
+
+/// Beam search over the mutation graph: keeps the top `beam_width` states by
+/// `tree.evaluate` at each depth, expands every frontier state through all
+/// its possible mutations, dedups against previously-seen states, and
+/// re-ranks the combined successors before truncating back to `beam_width`.
+/// Runs for `max_depth` steps or until no new successors are found,
+/// returning the best state seen at any point.
+pub fn beam_search<T>(
+    start_state: T,
+    tree: &dyn Evaluator<T>,
+    beam_width: usize,
+    max_depth: usize,
+) -> T
+where
+    T: State,
+{
+    let mutations = T::get_possible_mutations();
+
+    let mut previous_states = HashSet::new();
+    previous_states.insert(start_state);
+
+    let mut frontier = vec![start_state];
+    let mut best_state = start_state;
+    let mut best_evaluation = tree.evaluate(start_state);
+
+    for _ in 0..max_depth {
+        let mut successors: Vec<(T, i32)> = frontier
+            .iter()
+            .flat_map(|&state| expand_state(state, &mutations, &mut previous_states))
+            .map(|state| (state, tree.evaluate(state)))
+            .collect();
+
+        if successors.is_empty() {
+            break;
+        }
+
+        successors.sort_by_key(|&(_, evaluation)| std::cmp::Reverse(evaluation));
+        successors.truncate(beam_width);
+
+        for &(state, evaluation) in &successors {
+            if evaluation > best_evaluation {
+                best_state = state;
+                best_evaluation = evaluation;
+            }
+        }
+
+        frontier = successors.into_iter().map(|(state, _)| state).collect();
+    }
+
+    best_state
+}
+
+fn expand_state<T>(
+    state: T,
+    mutations: &Vec<Box<Mutation<T>>>,
+    previous_states: &mut HashSet<T>,
+) -> Vec<T>
+where
+    T: State,
+{
+    mutations
+        .iter()
+        .filter_map(|mutation| {
+            let child_state = mutation(state);
+            if previous_states.contains(&child_state) {
+                None
+            } else {
+                previous_states.insert(child_state);
+                Some(child_state)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::implementations::{
+        constants::{STATE_SIZE, STATE_VALUE_MAX},
+        state_array::StateArray,
+    };
+
+    struct SumEvaluator;
+
+    impl Evaluator<StateArray> for SumEvaluator {
+        fn evaluate(&self, state: StateArray) -> i32 {
+            state.0.iter().sum()
+        }
+    }
+
+    #[test]
+    fn climbs_toward_the_maximum_when_every_step_can_improve() {
+        let start_state = StateArray([0; STATE_SIZE]);
+        let tree = SumEvaluator;
+        let result = beam_search(start_state, &tree, 4, 10);
+        assert!(tree.evaluate(result) > tree.evaluate(start_state));
+    }
+
+    #[test]
+    fn never_descends_from_an_already_maximal_state() {
+        let start_state = StateArray([STATE_VALUE_MAX; STATE_SIZE]);
+        let tree = SumEvaluator;
+        let result = beam_search(start_state, &tree, 4, 10);
+        assert_eq!(result, start_state);
+    }
+}
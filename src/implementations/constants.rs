@@ -6,6 +6,4 @@ pub const STATE_VALUE_MAX : i32 = 50000;
 pub const NODE_VALUE_MIN : i32 = -50;
 pub const NODE_VALUE_MAX : i32 = 50;
 
-pub const HEURISTIC_SEARCH_DEPTH : usize = 0;
-
-pub const LOOP_PRINT_INTERVAL : u32 = 1000;
\ No newline at end of file
+pub const HEURISTIC_SEARCH_DEPTH : usize = 3;
\ No newline at end of file
@@ -0,0 +1,6 @@
+pub mod constants;
+pub mod evaluations_tree;
+pub mod rollout_strategy;
+pub mod state_array;
+pub mod surrogate_evaluator;
+pub mod utils;
@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+
+use crate::schedule::Schedule;
+use crate::types::{Evaluator, Mutation, Surrogate};
+
+use super::{state_array::StateArray, surrogate_evaluator::SurrogateEvaluator};
+
+/// Rolls `rollout_depth` steps forward from `state`, picking each step's
+/// mutation via the surrogate while within `SurrogateEvaluator::drives_step`
+/// and once the surrogate `is_trained`, otherwise epsilon-greedy against
+/// `tree`. Each proposed step is accepted or
+/// rejected via `schedule`'s Metropolis criterion, so a worsening move can
+/// still be taken (and then built on) while the schedule's temperature is
+/// high, annealing toward pure hill-climbing as `step` grows.
+pub fn rollout_strategy(
+    mut state: StateArray,
+    mutations: &&Vec<Box<Mutation<StateArray>>>,
+    tree: &dyn Evaluator<StateArray>,
+    rollout_depth: usize,
+    rollout_epsilon: f64,
+    schedule: &Schedule,
+    surrogate: Option<&dyn Surrogate<StateArray>>,
+    _previous_states: &HashSet<StateArray>,
+) -> i32 {
+    let mut rng = rand::thread_rng();
+    let mut current_evaluation = tree.evaluate(state);
+
+    for step in 0..rollout_depth {
+        let mutation_index = match surrogate
+            .filter(|surrogate| SurrogateEvaluator::drives_step(step) && surrogate.is_trained())
+        {
+            Some(surrogate) => surrogate.best_mutation_index(state, mutations.as_slice()),
+            None if rng.gen::<f64>() < rollout_epsilon => rng.gen_range(0..mutations.len()),
+            None => best_mutation_index(tree, state, mutations.as_slice()),
+        };
+
+        let next_state = mutations[mutation_index](state);
+        let next_evaluation = tree.evaluate(next_state);
+
+        if schedule.accept(current_evaluation, next_evaluation, step) {
+            state = next_state;
+            current_evaluation = next_evaluation;
+        }
+    }
+
+    current_evaluation
+}
+
+fn best_mutation_index(
+    tree: &dyn Evaluator<StateArray>,
+    state: StateArray,
+    mutations: &[Box<Mutation<StateArray>>],
+) -> usize {
+    let mut best_index = 0;
+    let mut best_score = i32::MIN;
+
+    for (index, mutation) in mutations.iter().enumerate() {
+        let score = tree.evaluate(mutation(state));
+
+        if score > best_score {
+            best_score = score;
+            best_index = index;
+        }
+    }
+
+    best_index
+}
@@ -0,0 +1,36 @@
+use crate::types::{Mutation, State};
+
+use super::constants::{STATE_SIZE, STATE_VALUE_MAX, STATE_VALUE_MIN};
+use super::utils;
+
+/// A state represented as a fixed-size array of `i32` coordinates.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct StateArray(pub [i32; STATE_SIZE]);
+
+impl StateArray {
+    pub fn random_state() -> StateArray {
+        StateArray(utils::random_array(STATE_VALUE_MIN, STATE_VALUE_MAX))
+    }
+}
+
+impl State for StateArray {
+    fn get_possible_mutations() -> Vec<Box<Mutation<StateArray>>> {
+        let mut mutations: Vec<Box<Mutation<StateArray>>> = Vec::with_capacity(STATE_SIZE * 2);
+
+        for index in 0..STATE_SIZE {
+            mutations.push(Box::new(move |state: StateArray| {
+                let mut next = state;
+                next.0[index] = (next.0[index] + 1).min(STATE_VALUE_MAX);
+                next
+            }));
+
+            mutations.push(Box::new(move |state: StateArray| {
+                let mut next = state;
+                next.0[index] = (next.0[index] - 1).max(STATE_VALUE_MIN);
+                next
+            }));
+        }
+
+        mutations
+    }
+}
@@ -0,0 +1,293 @@
+use crate::types::{Evaluator, Mutation, Surrogate};
+
+use super::{
+    constants::{HEURISTIC_SEARCH_DEPTH, STATE_SIZE},
+    state_array::StateArray,
+};
+
+/// A CART-style regression tree fitted online from `(state, true_evaluation)`
+/// pairs observed during search. Used to bias rollouts within the first
+/// `HEURISTIC_SEARCH_DEPTH` steps instead of picking a mutation blindly.
+pub struct SurrogateEvaluator {
+    tree: RegressionNode,
+    samples: Vec<([i32; STATE_SIZE], i32)>,
+    refit_interval: usize,
+    samples_since_refit: usize,
+    max_depth: usize,
+    has_fit: bool,
+}
+
+enum RegressionNode {
+    Leaf {
+        mean: f64,
+    },
+    Split {
+        feature_index: usize,
+        threshold: i32,
+        left: Box<RegressionNode>,
+        right: Box<RegressionNode>,
+    },
+}
+
+impl SurrogateEvaluator {
+    pub fn new(refit_interval: usize, max_depth: usize) -> SurrogateEvaluator {
+        SurrogateEvaluator {
+            tree: RegressionNode::Leaf { mean: 0.0 },
+            samples: Vec::new(),
+            refit_interval,
+            samples_since_refit: 0,
+            max_depth,
+            has_fit: false,
+        }
+    }
+
+    fn refit(&mut self) {
+        self.tree = build_node(&self.samples, self.max_depth);
+        self.has_fit = true;
+    }
+
+    /// Whether the given rollout step falls within the heuristic window, in
+    /// which the surrogate should drive the mutation choice instead of
+    /// epsilon-random exploration.
+    pub fn drives_step(step: usize) -> bool {
+        step < HEURISTIC_SEARCH_DEPTH
+    }
+}
+
+impl Evaluator<StateArray> for SurrogateEvaluator {
+    fn evaluate(&self, state: StateArray) -> i32 {
+        self.tree.predict(&state.0) as i32
+    }
+}
+
+impl Surrogate<StateArray> for SurrogateEvaluator {
+    /// Records a sample observed during search and periodically refits the
+    /// tree once enough new samples have accumulated.
+    fn record(&mut self, state: StateArray, true_evaluation: i32) {
+        self.samples.push((state.0, true_evaluation));
+        self.samples_since_refit += 1;
+
+        if self.samples_since_refit >= self.refit_interval {
+            self.refit();
+            self.samples_since_refit = 0;
+        }
+    }
+
+    /// Greedily picks the mutation the surrogate scores highest, for use in
+    /// place of an epsilon-random step during the heuristic portion of a
+    /// rollout.
+    fn best_mutation_index(
+        &self,
+        state: StateArray,
+        mutations: &[Box<Mutation<StateArray>>],
+    ) -> usize {
+        let mut best_index = 0;
+        let mut best_score = f64::MIN;
+
+        for (index, mutation) in mutations.iter().enumerate() {
+            let child_state = mutation(state);
+            let score = self.tree.predict(&child_state.0);
+
+            if score > best_score {
+                best_score = score;
+                best_index = index;
+            }
+        }
+
+        best_index
+    }
+
+    fn is_trained(&self) -> bool {
+        self.has_fit
+    }
+}
+
+impl RegressionNode {
+    fn predict(&self, features: &[i32; STATE_SIZE]) -> f64 {
+        match self {
+            RegressionNode::Leaf { mean } => *mean,
+            RegressionNode::Split {
+                feature_index,
+                threshold,
+                left,
+                right,
+            } => {
+                if features[*feature_index] <= *threshold {
+                    left.predict(features)
+                } else {
+                    right.predict(features)
+                }
+            }
+        }
+    }
+}
+
+fn build_node(samples: &[([i32; STATE_SIZE], i32)], max_depth: usize) -> RegressionNode {
+    if max_depth == 0 || samples.len() < 2 {
+        return RegressionNode::Leaf {
+            mean: mean_evaluation(samples),
+        };
+    }
+
+    match best_split(samples) {
+        Some((feature_index, threshold, left_samples, right_samples)) => RegressionNode::Split {
+            feature_index,
+            threshold,
+            left: Box::new(build_node(&left_samples, max_depth - 1)),
+            right: Box::new(build_node(&right_samples, max_depth - 1)),
+        },
+        None => RegressionNode::Leaf {
+            mean: mean_evaluation(samples),
+        },
+    }
+}
+
+/// Greedily chooses the (feature, threshold) split that minimizes the
+/// visit-count-weighted sum of child variances.
+#[allow(clippy::type_complexity)]
+fn best_split(
+    samples: &[([i32; STATE_SIZE], i32)],
+) -> Option<(
+    usize,
+    i32,
+    Vec<([i32; STATE_SIZE], i32)>,
+    Vec<([i32; STATE_SIZE], i32)>,
+)> {
+    let mut best: Option<(usize, i32, f64)> = None;
+
+    for feature_index in 0..STATE_SIZE {
+        let mut thresholds: Vec<i32> = samples
+            .iter()
+            .map(|(features, _)| features[feature_index])
+            .collect();
+        thresholds.sort_unstable();
+        thresholds.dedup();
+
+        for threshold in thresholds {
+            let (left, right): (Vec<_>, Vec<_>) = samples
+                .iter()
+                .partition(|(features, _)| features[feature_index] <= threshold);
+
+            if left.is_empty() || right.is_empty() {
+                continue;
+            }
+
+            let weighted_variance = left.len() as f64 * variance(&left)
+                + right.len() as f64 * variance(&right);
+
+            if best.is_none_or(|(_, _, best_variance)| weighted_variance < best_variance) {
+                best = Some((feature_index, threshold, weighted_variance));
+            }
+        }
+    }
+
+    best.map(|(feature_index, threshold, _)| {
+        let (left, right): (Vec<_>, Vec<_>) = samples
+            .iter()
+            .cloned()
+            .partition(|(features, _)| features[feature_index] <= threshold);
+
+        (feature_index, threshold, left, right)
+    })
+}
+
+fn mean_evaluation(samples: &[([i32; STATE_SIZE], i32)]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let sum: i64 = samples.iter().map(|(_, evaluation)| *evaluation as i64).sum();
+    sum as f64 / samples.len() as f64
+}
+
+fn variance(samples: &[([i32; STATE_SIZE], i32)]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let mean = mean_evaluation(samples);
+    let sum_squared_diff: f64 = samples
+        .iter()
+        .map(|(_, evaluation)| {
+            let diff = *evaluation as f64 - mean;
+            diff * diff
+        })
+        .sum();
+
+    sum_squared_diff / samples.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(feature_0: i32, evaluation: i32) -> ([i32; STATE_SIZE], i32) {
+        let mut features = [0; STATE_SIZE];
+        features[0] = feature_0;
+        (features, evaluation)
+    }
+
+    #[test]
+    fn variance_of_identical_evaluations_is_zero() {
+        let samples = vec![sample(0, 10), sample(1, 10), sample(2, 10)];
+
+        assert_eq!(variance(&samples), 0.0);
+    }
+
+    #[test]
+    fn variance_matches_hand_computed_value() {
+        let samples = vec![sample(0, 0), sample(0, 10)];
+
+        assert_eq!(variance(&samples), 25.0);
+    }
+
+    #[test]
+    fn best_split_separates_a_clean_two_cluster_dataset() {
+        let samples = vec![
+            sample(0, 0),
+            sample(0, 0),
+            sample(10, 100),
+            sample(10, 100),
+        ];
+
+        let (feature_index, threshold, left, right) =
+            best_split(&samples).expect("a clean split should be found");
+
+        assert_eq!(feature_index, 0);
+        assert_eq!(threshold, 0);
+        assert_eq!(left.len(), 2);
+        assert_eq!(right.len(), 2);
+    }
+
+    #[test]
+    fn best_split_returns_none_when_no_feature_separates_the_data() {
+        let samples = vec![sample(0, 0), sample(0, 100)];
+
+        assert!(best_split(&samples).is_none());
+    }
+
+    #[test]
+    fn build_node_fits_a_clean_two_cluster_dataset_exactly() {
+        let samples = vec![
+            sample(0, 0),
+            sample(0, 0),
+            sample(10, 100),
+            sample(10, 100),
+        ];
+
+        let tree = build_node(&samples, 2);
+
+        for (features, evaluation) in &samples {
+            assert_eq!(tree.predict(features), *evaluation as f64);
+        }
+    }
+
+    #[test]
+    fn build_node_falls_back_to_a_leaf_at_zero_depth() {
+        let samples = vec![sample(0, 0), sample(10, 100)];
+
+        let tree = build_node(&samples, 0);
+
+        assert!(matches!(tree, RegressionNode::Leaf { .. }));
+    }
+}
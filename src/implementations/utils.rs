@@ -0,0 +1,18 @@
+use rand::Rng;
+
+use super::constants::STATE_SIZE;
+
+pub fn dot_product(a: [i32; STATE_SIZE], b: [i32; STATE_SIZE]) -> i32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+pub fn random_array(min: i32, max: i32) -> [i32; STATE_SIZE] {
+    let mut rng = rand::thread_rng();
+    let mut array = [0; STATE_SIZE];
+
+    for value in array.iter_mut() {
+        *value = rng.gen_range(min..max);
+    }
+
+    array
+}
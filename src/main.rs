@@ -1,8 +1,12 @@
 mod types;
 mod implementations;
 mod tree_search;
+mod beam_search;
+mod schedule;
 
-use crate::{types::State, implementations::{state_array, evaluations_tree, rollout_strategy::rollout_strategy}, tree_search::search};
+use std::time::Duration;
+
+use crate::{implementations::{state_array, evaluations_tree, rollout_strategy::rollout_strategy, surrogate_evaluator::SurrogateEvaluator}, tree_search::{search, SearchConfig, StopCondition, Uct}, beam_search::beam_search, schedule::Schedule};
 
 
 fn main() {
@@ -12,7 +16,41 @@ fn main() {
 
     println!("START: {}", tree.evaluate(start_state));
 
-    let searched_state = search(start_state, rollout_strategy, &tree, 500, 0.3, 2.0);
+    let policy = Uct::default();
+
+    let schedule = Schedule {
+        start_temp: 50.0,
+        decay: 0.95,
+    };
+
+    let mut surrogate = SurrogateEvaluator::new(50, 3);
+
+    let report_progress = |progress: &tree_search::SearchProgress| {
+        println!(
+            "[{:?}] iterations={} tree_size={} best={}",
+            progress.elapsed, progress.iterations, progress.tree_size, progress.best_evaluation
+        );
+    };
+
+    let (searched_state, _tree_size) = search(
+        start_state,
+        rollout_strategy,
+        tree.as_ref(),
+        StopCondition::TimeBudget(Duration::from_millis(1000)),
+        SearchConfig {
+            rollout_depth: 5,
+            rollout_epsilon: 0.3,
+            schedule: &schedule,
+            surrogate: Some(&mut surrogate),
+            policy: &policy,
+            move_away_constant: 0.1,
+        },
+        Some((Duration::from_millis(100), &report_progress)),
+    );
 
     println!("AFTER SEARCH: {}", tree.evaluate(searched_state));
+
+    let beam_searched_state = beam_search(start_state, tree.as_ref(), 20, 10);
+
+    println!("AFTER BEAM SEARCH: {}", tree.evaluate(beam_searched_state));
 }
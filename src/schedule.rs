@@ -0,0 +1,29 @@
+/// Geometric temperature schedule used to anneal rollout acceptance: starts
+/// at `start_temp` and decays by `decay` per step, so early rollout steps
+/// explore broadly and later ones exploit.
+pub struct Schedule {
+    pub start_temp: f64,
+    pub decay: f64,
+}
+
+impl Schedule {
+    /// Temperature after `step` rollout steps, decaying geometrically from
+    /// `start_temp` but never collapsing all the way to zero.
+    pub fn temperature_at(&self, step: usize) -> f64 {
+        (self.start_temp * self.decay.powi(step as i32)).max(f64::EPSILON)
+    }
+
+    /// Metropolis acceptance criterion: an improving or equal mutation is
+    /// always accepted, while a worsening one is still accepted with
+    /// probability `exp((new_eval - current_eval) / temperature)`.
+    pub fn accept(&self, current_eval: i32, new_eval: i32, step: usize) -> bool {
+        if new_eval >= current_eval {
+            return true;
+        }
+
+        let temperature = self.temperature_at(step);
+        let acceptance_probability = ((new_eval - current_eval) as f64 / temperature).exp();
+
+        rand::random::<f64>() < acceptance_probability
+    }
+}
@@ -1,24 +1,208 @@
 use rand::Rng;
-use std::collections::HashSet;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::schedule::Schedule;
+use crate::types::{Evaluator, Mutation, RollOut, State, Surrogate};
+
+/// When to stop searching: after a fixed number of iterations, or once a
+/// wall-clock time budget has been spent.
+#[derive(Clone, Copy)]
+pub enum StopCondition {
+    Iterations(u32),
+    TimeBudget(Duration),
+}
+
+/// Snapshot of search progress, reported to a caller-supplied callback every
+/// `progress_interval` while the search runs.
+pub struct SearchProgress {
+    pub iterations: u32,
+    pub elapsed: Duration,
+    pub tree_size: u32,
+    pub best_evaluation: i32,
+}
+
+/// Per-child statistics exposed to a [`TreePolicy`] when it has to pick which
+/// child to descend into next.
+pub struct ChildStats {
+    pub average_evaluation: f64,
+    pub times_visited: u32,
+    pub variance: f64,
+}
+
+/// Strategy used by [`TreeSearchNode`] to pick which child to descend into
+/// during the selection phase of the search.
+pub trait TreePolicy {
+    fn choose_child(&self, parent_visits: u32, children: &[ChildStats]) -> usize;
+}
+
+/// Classic UCT selection: exploit the child with the best average
+/// evaluation, with an exploration bonus weighted by `exploration_constant`.
+pub struct Uct {
+    pub exploration_constant: f64,
+}
 
-use crate::{
-    implementations::constants::LOOP_PRINT_INTERVAL,
-    types::{Evaluator, Mutation, RollOut, State},
-};
+impl Default for Uct {
+    fn default() -> Self {
+        Uct {
+            exploration_constant: 2.0,
+        }
+    }
+}
+
+impl TreePolicy for Uct {
+    fn choose_child(&self, parent_visits: u32, children: &[ChildStats]) -> usize {
+        let mut best_index = 0;
+        let mut best_score = f64::MIN;
+
+        for (index, child) in children.iter().enumerate() {
+            let score = ucb(
+                child.average_evaluation,
+                self.exploration_constant,
+                child.times_visited,
+                parent_visits,
+            );
 
-pub fn search<T>(
+            if score > best_score {
+                best_score = score;
+                best_index = index;
+            }
+        }
+
+        best_index
+    }
+}
+
+/// UCB1-tuned selection: like [`Uct`], but the exploration bonus is scaled by
+/// an estimate of each child's reward variance, which tends to explore less
+/// once a child's returns are known to be stable.
+///
+/// The `min(1/4, V_n)` variance bound in the original UCB1-tuned formula
+/// assumes rewards live in `[0, 1]` (a Bernoulli variable's variance is
+/// capped at 1/4); this crate's evaluations are raw dot-products that can run
+/// into the tens of thousands, so `reward_range` rescales evaluations into
+/// that unit interval before the bound is applied. Without it the bonus is
+/// negligible next to `average_evaluation` and the policy degenerates to
+/// pure greedy selection.
+pub struct Ucb1Tuned {
+    pub reward_range: f64,
+}
+
+impl Ucb1Tuned {
+    pub fn new(reward_range: f64) -> Ucb1Tuned {
+        Ucb1Tuned { reward_range }
+    }
+}
+
+impl TreePolicy for Ucb1Tuned {
+    fn choose_child(&self, parent_visits: u32, children: &[ChildStats]) -> usize {
+        let log_term = (parent_visits as f64).ln();
+
+        let mut best_index = 0;
+        let mut best_score = f64::MIN;
+
+        for (index, child) in children.iter().enumerate() {
+            if child.times_visited == 0 {
+                return index;
+            }
+
+            let n = child.times_visited as f64;
+            let normalized_average = child.average_evaluation / self.reward_range;
+            let normalized_variance = child.variance / (self.reward_range * self.reward_range);
+            let variance_bound = 0.25_f64.min(normalized_variance + (2.0 * log_term / n).sqrt());
+            let score = normalized_average + ((log_term / n) * variance_bound).sqrt();
+
+            if score > best_score {
+                best_score = score;
+                best_index = index;
+            }
+        }
+
+        best_index
+    }
+}
+
+/// Selection policy that ignores the statistics entirely and picks a child
+/// at random, useful as a baseline against the UCT-style policies.
+pub struct RandomPolicy;
+
+impl TreePolicy for RandomPolicy {
+    fn choose_child(&self, _parent_visits: u32, children: &[ChildStats]) -> usize {
+        let mut rng = rand::thread_rng();
+        rng.gen_range(0..children.len())
+    }
+}
+
+/// Tuning knobs, the annealing schedule, the selection policy, and the
+/// optional surrogate for a single (non-parallel) search run, grouped to
+/// keep `search`/`run_search`/[`TreeSearchNode::run`]/
+/// [`TreeSearchNode::simulate`] from drowning in positional arguments.
+pub struct SearchConfig<'a, T, P> {
+    pub rollout_depth: usize,
+    pub rollout_epsilon: f64,
+    pub schedule: &'a Schedule,
+    pub surrogate: Option<&'a mut dyn Surrogate<T>>,
+    pub policy: &'a P,
+    pub move_away_constant: f64,
+}
+
+/// Same tuning knobs as [`SearchConfig`], but without a surrogate: a single
+/// surrogate can't be fit consistently from `num_threads` independent trees
+/// running concurrently, so [`search_parallel`] doesn't support one.
+pub struct ParallelSearchConfig<'a, P> {
+    pub rollout_depth: usize,
+    pub rollout_epsilon: f64,
+    pub schedule: &'a Schedule,
+    pub policy: &'a P,
+    pub move_away_constant: f64,
+}
+
+pub fn search<T, P>(
     start_state: T,
     rollout: RollOut<T>,
     tree: &dyn Evaluator<T>,
-    loops: u32,
-    rollout_depth: usize,
-    rollout_epsilon: f64,
-    uct_exploration: f64,
-    move_away_constant: f64,
-    random_search: bool,
+    stop_condition: StopCondition,
+    config: SearchConfig<T, P>,
+    progress_callback: Option<(Duration, &dyn Fn(&SearchProgress))>,
 ) -> (T, u32)
 where
     T: State,
+    P: TreePolicy,
+{
+    let result = run_search(start_state, rollout, tree, stop_condition, config, progress_callback);
+
+    (result.best_state, result.tree_size)
+}
+
+/// Result of growing a single search tree: the best state found, the tree's
+/// size, and a per-root-child summary used by [`search_parallel`] to merge
+/// independent trees by visit count and best-achieved evaluation.
+struct SearchResult<T> {
+    best_state: T,
+    tree_size: u32,
+    child_summaries: Vec<ChildSummary<T>>,
+}
+
+/// Summary of a single root-level child action, as seen by one search tree.
+struct ChildSummary<T> {
+    action_state: T,
+    times_visited: u32,
+    best_state: T,
+    best_evaluation: i32,
+}
+
+fn run_search<T, P>(
+    start_state: T,
+    rollout: RollOut<T>,
+    tree: &dyn Evaluator<T>,
+    stop_condition: StopCondition,
+    mut config: SearchConfig<T, P>,
+    progress_callback: Option<(Duration, &dyn Fn(&SearchProgress))>,
+) -> SearchResult<T>
+where
+    T: State,
+    P: TreePolicy,
 {
     let mutations = T::get_possible_mutations();
 
@@ -26,27 +210,148 @@ where
 
     let mut base_node = TreeSearchNode::new(start_state, &mutations, &mut previous_states);
 
-    for loop_number in 0..loops {
-        if loop_number % LOOP_PRINT_INTERVAL == 0 {
-            println!("Loop {}", loop_number);
+    let start_time = Instant::now();
+    let mut iterations = 0;
+    let mut last_reported_at = Duration::ZERO;
+
+    loop {
+        match stop_condition {
+            StopCondition::Iterations(max_iterations) => {
+                if iterations >= max_iterations {
+                    break;
+                }
+            }
+            StopCondition::TimeBudget(budget) => {
+                if start_time.elapsed() >= budget {
+                    break;
+                }
+            }
         }
 
-        base_node.run(
-            uct_exploration,
-            rollout,
-            tree,
-            rollout_epsilon,
-            rollout_depth,
-            &mut previous_states,
-            move_away_constant,
-            random_search,
-        );
+        base_node.run(rollout, tree, &mut config, &mut previous_states);
+        iterations += 1;
+
+        if let Some((interval, callback)) = progress_callback {
+            let elapsed = start_time.elapsed();
+            if elapsed - last_reported_at >= interval {
+                callback(&SearchProgress {
+                    iterations,
+                    elapsed,
+                    tree_size: base_node.get_tree_size(),
+                    best_evaluation: tree.evaluate(base_node.get_max_state(tree)),
+                });
+                last_reported_at = elapsed;
+            }
+        }
+    }
+
+    SearchResult {
+        best_state: base_node.get_max_state(tree),
+        tree_size: base_node.get_tree_size(),
+        child_summaries: base_node.child_summaries(tree),
+    }
+}
+
+/// Root-parallel MCTS: runs `num_threads` independent searches, each with its
+/// own tree and its own `previous_states` set, then returns the best state
+/// found across all of them. `Evaluator<T>` and the rollout are read-only
+/// during search, so the only extra requirement over [`search`] is that `T`,
+/// the evaluator and the policy can be shared across threads.
+///
+/// Each tree is merged by summed visit count and best-achieved evaluation per
+/// root-level child action, and the action with the highest summed visit
+/// count (breaking ties by best evaluation) gives a candidate state. Like
+/// [`search`], which returns the true max via `get_max_state`, the state
+/// returned here is never worse than any state observed by any of the
+/// `num_threads` trees: it's the better of that most-visited candidate and
+/// the single best-achieved state across all trees, so a high-eval state
+/// living under a less-visited action is never discarded.
+pub fn search_parallel<T, P>(
+    start_state: T,
+    rollout: RollOut<T>,
+    tree: &(dyn Evaluator<T> + Sync),
+    stop_condition: StopCondition,
+    config: ParallelSearchConfig<P>,
+    num_threads: usize,
+) -> (T, u32)
+where
+    T: State + Send + Sync,
+    P: TreePolicy + Sync,
+{
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .expect("failed to build rayon thread pool");
+
+    let results: Vec<SearchResult<T>> = pool.install(|| {
+        (0..num_threads)
+            .into_par_iter()
+            .map(|_| {
+                run_search(
+                    start_state,
+                    rollout,
+                    tree,
+                    stop_condition,
+                    SearchConfig {
+                        rollout_depth: config.rollout_depth,
+                        rollout_epsilon: config.rollout_epsilon,
+                        schedule: config.schedule,
+                        surrogate: None,
+                        policy: config.policy,
+                        move_away_constant: config.move_away_constant,
+                    },
+                    None,
+                )
+            })
+            .collect()
+    });
+
+    let mut merged: HashMap<T, (u32, T, i32)> = HashMap::new();
+
+    for result in &results {
+        for child in &result.child_summaries {
+            merged
+                .entry(child.action_state)
+                .and_modify(|(times_visited, best_state, best_evaluation)| {
+                    *times_visited += child.times_visited;
+                    if child.best_evaluation > *best_evaluation {
+                        *best_state = child.best_state;
+                        *best_evaluation = child.best_evaluation;
+                    }
+                })
+                .or_insert((child.times_visited, child.best_state, child.best_evaluation));
+        }
     }
 
-    let best_state = base_node.get_max_state(tree);
-    let tree_size = base_node.get_tree_size();
+    let total_tree_size = results.iter().map(|result| result.tree_size).sum();
+
+    let most_visited_action_state = merged
+        .into_values()
+        .max_by(|(visits_a, _, eval_a), (visits_b, _, eval_b)| {
+            visits_a.cmp(visits_b).then(eval_a.cmp(eval_b))
+        })
+        .map(|(_, best_state, _)| best_state);
 
-    (best_state, tree_size)
+    let global_best_state = results
+        .into_iter()
+        .map(|result| result.best_state)
+        .max_by_key(|&state| tree.evaluate(state))
+        .expect("num_threads must be greater than zero");
+
+    let best_state = match most_visited_action_state {
+        Some(state) if tree.evaluate(state) >= tree.evaluate(global_best_state) => state,
+        _ => global_best_state,
+    };
+
+    (best_state, total_tree_size)
+}
+
+/// Tracks how far a node has progressed through `T::get_possible_mutations()`.
+/// A node only ever materializes one new child per visit, so most of the
+/// mutation set can stay unexplored for states that have many mutations.
+enum Expansion {
+    Unexpanded { next_mutation_index: usize },
+    Expanded,
 }
 
 struct TreeSearchNode<'a, T>
@@ -55,9 +360,11 @@ where
 {
     times_visited: u32,
     average_evaluation: f64,
+    evaluation_m2: f64,
     state: T,
     children: Vec<TreeSearchNode<'a, T>>,
     mutations: &'a Vec<Box<Mutation<T>>>,
+    expansion: Expansion,
 }
 
 impl<'a, T> TreeSearchNode<'a, T>
@@ -72,9 +379,13 @@ where
         let new_node = TreeSearchNode {
             times_visited: 0,
             average_evaluation: 0.0,
+            evaluation_m2: 0.0,
             state,
             children: Vec::new(),
             mutations,
+            expansion: Expansion::Unexpanded {
+                next_mutation_index: 0,
+            },
         };
 
         previous_states.insert(state);
@@ -82,119 +393,153 @@ where
         new_node
     }
 
-    fn run(
+    fn run<P>(
         &mut self,
-        uct_exploration: f64,
         rollout: RollOut<T>,
         tree: &dyn Evaluator<T>,
-        rollout_epsilon: f64,
-        rollout_depth: usize,
+        config: &mut SearchConfig<T, P>,
         previous_states: &mut HashSet<T>,
-        move_away_constant: f64,
-        random_search: bool,
-    ) -> i32 {
-        if self.children.is_empty() {
-            let (expanded, no_non_explored_states) = self.expand(previous_states);
-
-            if no_non_explored_states {
-                let value = (self.average_evaluation
-                    - self.average_evaluation.abs() * move_away_constant)
-                    as i32;
-                self.update_average(value);
-                return value;
+    ) -> i32
+    where
+        P: TreePolicy,
+    {
+        if self.times_visited == 0 {
+            let value = self.simulate(rollout, tree, config, previous_states);
+            if let Some(surrogate) = config.surrogate.as_deref_mut() {
+                surrogate.record(self.state, tree.evaluate(self.state));
             }
+            self.update_average(value);
+            return value;
+        }
 
-            let value = expanded.simulate(
-                rollout,
-                tree,
-                rollout_epsilon,
-                rollout_depth,
-                previous_states,
-            );
+        if let Some(child_index) = self.expand_one(previous_states) {
+            let value = self.children[child_index].simulate(rollout, tree, config, previous_states);
+            if let Some(surrogate) = config.surrogate.as_deref_mut() {
+                let child_state = self.children[child_index].state;
+                surrogate.record(child_state, tree.evaluate(child_state));
+            }
+            self.children[child_index].update_average(value);
             self.update_average(value);
             return value;
         }
 
-        let best_index = self.best_ucb_score_index(uct_exploration, random_search);
+        if self.children.is_empty() {
+            let value = (self.average_evaluation
+                - self.average_evaluation.abs() * config.move_away_constant)
+                as i32;
+            self.update_average(value);
+            return value;
+        }
 
-        let value = self.children[best_index].run(
-            uct_exploration,
-            rollout,
-            tree,
-            rollout_epsilon,
-            rollout_depth,
-            previous_states,
-            move_away_constant,
-            random_search,
-        );
+        let best_index = self.choose_child_index(config.policy);
+
+        let value = self.children[best_index].run(rollout, tree, config, previous_states);
         self.update_average(value);
         value
     }
 
-    fn expand(&mut self, previous_states: &mut HashSet<T>) -> (&TreeSearchNode<T>, bool) {
-        if self.times_visited == 0 {
-            return (self, false);
-        }
+    /// Materializes at most one new child from the next unexplored mutation,
+    /// skipping mutations that lead to a previously-seen state. Returns the
+    /// index of the freshly materialized child, or `None` once every
+    /// mutation has been tried (marking this node fully expanded).
+    fn expand_one(&mut self, previous_states: &mut HashSet<T>) -> Option<usize> {
+        loop {
+            let next_mutation_index = match self.expansion {
+                Expansion::Expanded => return None,
+                Expansion::Unexpanded {
+                    next_mutation_index,
+                } => next_mutation_index,
+            };
+
+            if next_mutation_index >= self.mutations.len() {
+                self.expansion = Expansion::Expanded;
+                return None;
+            }
 
-        self.children = get_children_from_mutations(self.state, self.mutations, previous_states);
+            self.expansion = Expansion::Unexpanded {
+                next_mutation_index: next_mutation_index + 1,
+            };
 
-        if self.children.is_empty() {
-            return (self, true);
-        }
+            let child_state = self.mutations[next_mutation_index](self.state);
 
-        (&self.children[0], false)
+            if previous_states.contains(&child_state) {
+                continue;
+            }
+
+            self.children
+                .push(TreeSearchNode::new(child_state, self.mutations, previous_states));
+            return Some(self.children.len() - 1);
+        }
     }
 
-    fn simulate(
+    fn simulate<P>(
         &self,
         rollout: RollOut<T>,
         tree: &dyn Evaluator<T>,
-        rollout_epsilon: f64,
-        rollout_depth: usize,
+        config: &SearchConfig<T, P>,
         previous_states: &HashSet<T>,
     ) -> i32 {
         rollout(
             self.state,
             &self.mutations,
             tree,
-            rollout_depth,
-            rollout_epsilon,
+            config.rollout_depth,
+            config.rollout_epsilon,
+            config.schedule,
+            config.surrogate.as_deref(),
             previous_states,
         )
     }
 
-    fn best_ucb_score_index(&self, uct_exploration: f64, random_search: bool) -> usize {
-        if random_search {
-            let mut rng = rand::thread_rng();
-            return rng.gen_range(0..self.children.len());
-        }
-
-        let mut best_ucb_score = 0.0;
-        let mut best_index = 0;
-
-        for index in 0..self.children.len() {
-            let child = &self.children[index];
+    /// Per-root-child visit count and best-achieved evaluation, used by
+    /// [`search_parallel`] to merge independent trees by root-level action
+    /// rather than just picking the best tree's overall best state.
+    fn child_summaries(&self, tree: &dyn Evaluator<T>) -> Vec<ChildSummary<T>> {
+        self.children
+            .iter()
+            .map(|child| {
+                let best_state = child.get_max_state(tree);
+                ChildSummary {
+                    action_state: child.state,
+                    times_visited: child.times_visited,
+                    best_state,
+                    best_evaluation: tree.evaluate(best_state),
+                }
+            })
+            .collect()
+    }
 
-            let child_ubc_score = ucb(
-                child.average_evaluation,
-                uct_exploration,
-                child.times_visited,
-                self.times_visited,
-            );
+    fn choose_child_index<P>(&self, policy: &P) -> usize
+    where
+        P: TreePolicy,
+    {
+        let child_stats: Vec<ChildStats> = self
+            .children
+            .iter()
+            .map(|child| ChildStats {
+                average_evaluation: child.average_evaluation,
+                times_visited: child.times_visited,
+                variance: child.variance(),
+            })
+            .collect();
+
+        policy.choose_child(self.times_visited, &child_stats)
+    }
 
-            if child_ubc_score > best_ucb_score {
-                best_ucb_score = child_ubc_score;
-                best_index = index;
-            }
+    fn variance(&self) -> f64 {
+        if self.times_visited > 1 {
+            self.evaluation_m2 / self.times_visited as f64
+        } else {
+            0.0
         }
-
-        best_index
     }
 
     fn update_average(&mut self, value: i32) {
         self.times_visited += 1;
-        self.average_evaluation +=
-            (value as f64 - self.average_evaluation) / self.times_visited as f64;
+        let delta = value as f64 - self.average_evaluation;
+        self.average_evaluation += delta / self.times_visited as f64;
+        let delta2 = value as f64 - self.average_evaluation;
+        self.evaluation_m2 += delta * delta2;
     }
 
     fn get_max_state(&self, tree: &dyn Evaluator<T>) -> T {
@@ -235,25 +580,3 @@ fn ucb(
     let log_term = (total_times_visited as f64).ln();
     average_evaluation + uct_exploration * (log_term / (times_visited as f64)).sqrt()
 }
-
-fn get_children_from_mutations<'a, T>(
-    state: T,
-    mutations: &'a Vec<Box<Mutation<T>>>,
-    previous_states: &mut HashSet<T>,
-) -> Vec<TreeSearchNode<'a, T>>
-where
-    T: State,
-{
-    mutations
-        .iter()
-        .filter_map(|mutation| {
-            let child_state = mutation(state);
-            if previous_states.contains(&child_state) {
-                None
-            } else {
-                previous_states.insert(child_state);
-                Some(TreeSearchNode::new(child_state, mutations, previous_states))
-            }
-        })
-        .collect()
-}
@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::schedule::Schedule;
+
+/// A state in the search space. Implementors are small, `Copy` values that
+/// can be hashed and compared so the tree search can dedup states it has
+/// already visited.
+pub trait State: Copy + Eq + Hash {
+    fn get_possible_mutations() -> Vec<Box<Mutation<Self>>>;
+}
+
+/// Scores a state; higher is better. This is the nondifferentiable function
+/// being maximized.
+pub trait Evaluator<T> {
+    fn evaluate(&self, state: T) -> i32;
+}
+
+/// A single mutation taking one state to another.
+pub type Mutation<T> = dyn Fn(T) -> T;
+
+/// A surrogate for `Evaluator<T>` that can be fitted online from observed
+/// `(state, true_evaluation)` pairs seen during search, and consulted to
+/// greedily pick a mutation in place of epsilon-random exploration.
+pub trait Surrogate<T>: Evaluator<T> {
+    fn record(&mut self, state: T, true_evaluation: i32);
+
+    fn best_mutation_index(&self, state: T, mutations: &[Box<Mutation<T>>]) -> usize;
+
+    /// Whether the surrogate has been fit on at least one batch of recorded
+    /// samples. Callers should fall back to a different mutation-selection
+    /// strategy until this is `true`, since an untrained surrogate's
+    /// predictions are meaningless.
+    fn is_trained(&self) -> bool;
+}
+
+/// A rollout strategy: given a state to roll out from, the full mutation
+/// set, the evaluator, a rollout depth/epsilon, the annealing schedule, an
+/// optional surrogate to drive the heuristic portion of the rollout, and
+/// the set of states already seen elsewhere in the tree, returns the
+/// evaluation reached at the end of the rollout.
+pub type RollOut<T> = fn(
+    T,
+    &&Vec<Box<Mutation<T>>>,
+    &dyn Evaluator<T>,
+    usize,
+    f64,
+    &Schedule,
+    Option<&dyn Surrogate<T>>,
+    &HashSet<T>,
+) -> i32;